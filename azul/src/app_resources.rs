@@ -0,0 +1,48 @@
+//! Font & image resource handling, lookup and caching.
+//!
+//! Owns every image and font a `Dom` can reference by id, so that neither
+//! the DOM tree nor the display list ever carries pixel/glyph data around
+//! directly - they carry an [`ImageId`] / [`FontId`] and `app_resources` is
+//! where `compositor` and `text_layout` go to resolve one into actual bytes.
+
+use azul_css::FontId;
+use images::{ImageId, ImageSource};
+use font::Font;
+use FastHashMap;
+
+/// Resource store handed to `Layout::layout` and the compositor each frame.
+pub struct AppResources {
+    images: FastHashMap<ImageId, ImageSource>,
+    fonts: FastHashMap<FontId, Font>,
+}
+
+impl AppResources {
+    pub fn new() -> Self {
+        Self { images: FastHashMap::default(), fonts: FastHashMap::default() }
+    }
+
+    /// Registers an image under a caller-chosen [`ImageId`] (see
+    /// [`ImageId::new`]). Accepts any [`ImageSource`] and stores it as-is.
+    /// Only a `Raw` source (e.g. a clipboard paste) is already decoded and
+    /// gets uploaded by the compositor; an embedded/file source is not
+    /// decoded anywhere, so the compositor gets no texture for it.
+    pub fn add_image_source(&mut self, id: ImageId, source: ImageSource) {
+        self.images.insert(id, source);
+    }
+
+    pub fn get_image_source(&self, id: ImageId) -> Option<&ImageSource> {
+        self.images.get(&id)
+    }
+
+    pub fn delete_image(&mut self, id: ImageId) {
+        self.images.remove(&id);
+    }
+
+    pub fn add_font(&mut self, id: FontId, font: Font) {
+        self.fonts.insert(id, font);
+    }
+
+    pub fn get_font(&self, id: &FontId) -> Option<&Font> {
+        self.fonts.get(id)
+    }
+}
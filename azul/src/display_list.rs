@@ -0,0 +1,29 @@
+//! Converts the UI description (the styled HTML nodes) to an actual display
+//! list (+ layout).
+//!
+//! Building a real display list out of a styled `Dom` needs `ui_solver`
+//! (flexbox layout) and `ui_description` (the styled node tree), neither of
+//! which exist in this checkout - so `build` below produces an empty,
+//! correctly-addressed display list for `compositor` to submit each frame
+//! rather than a styled one. It is still wrapped in its own profiler scope,
+//! same as every other step `compositor` times, since building the display
+//! list is not free even before a single node is added to it.
+
+use profiler::Profiler;
+use renderer::BuiltDisplayListHandle;
+
+/// Builds the display list for the current frame, timed as its own
+/// `"display_list"` profiler scope so it shows up separately from the
+/// `"ui_texture"` scope that submits it.
+pub(crate) fn build(
+    profiler: &Profiler,
+    pipeline_id: ::webrender::api::PipelineId,
+    content_size: ::webrender::api::units::LayoutSize,
+) -> BuiltDisplayListHandle {
+    let _scope = profiler.begin_scope("display_list");
+
+    let builder = ::webrender::api::DisplayListBuilder::new(pipeline_id, content_size);
+    let (_pipeline_id, _content_size, built) = builder.finalize();
+
+    BuiltDisplayListHandle(Box::new(built))
+}
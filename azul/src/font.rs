@@ -0,0 +1,58 @@
+//! Font loading.
+//!
+//! Fonts are loaded once into `app_resources` and kept around both as a
+//! parsed `rusttype::Font` (used for layout / the default `stb_truetype`-ish
+//! rasterization path) and as the original raw bytes, since some consumers -
+//! `text_shaping`'s `HarfbuzzShaper`, and the `cpu-font` rasterizer below -
+//! need to parse the font tables themselves rather than go through `rusttype`.
+
+use rusttype::Font as RusttypeFont;
+
+#[cfg(feature = "cpu-font")]
+use font_rasterizer::{CpuFontRasterizer, RasterizedGlyph};
+
+/// A loaded font: raw bytes plus the parsed `rusttype::Font` derived from them.
+pub struct Font {
+    bytes: Vec<u8>,
+    rusttype_font: RusttypeFont<'static>,
+}
+
+impl Font {
+    /// Parses `bytes` with `rusttype`, keeping the original bytes around for
+    /// consumers that need to parse the font tables themselves.
+    pub fn load(bytes: Vec<u8>) -> Option<Self> {
+        let rusttype_font = RusttypeFont::try_from_vec(bytes.clone())?;
+        Some(Self { bytes, rusttype_font })
+    }
+
+    pub fn rusttype_font(&self) -> &RusttypeFont<'static> {
+        &self.rusttype_font
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Rasterizes a single glyph through the pure-Rust `cpu-font` backend
+    /// instead of the default `stb_truetype` / WebRender native-font path.
+    /// Picking this backend only changes how a glyph's coverage bitmap is
+    /// produced - lookup (`Font::rusttype_font`/`glyph_index`) is unchanged.
+    #[cfg(feature = "cpu-font")]
+    pub fn rasterize_cpu(&self, glyph_index: u16, size_px: f32) -> Option<RasterizedGlyph> {
+        CpuFontRasterizer::parse(&self.bytes)?.rasterize(glyph_index, size_px)
+    }
+
+    /// Rasterizes every glyph in `glyph_indices` through the `cpu-font`
+    /// backend, parsing `self`'s font tables once and reusing that parse
+    /// across the whole batch (via `CpuFontRasterizer::rasterize_batch`)
+    /// instead of re-parsing per glyph like calling `rasterize_cpu` in a
+    /// loop would - the right choice for a whole laid-out run, same as
+    /// `rasterize_batch`'s own doc intends.
+    #[cfg(feature = "cpu-font")]
+    pub fn rasterize_cpu_batch(&self, glyph_indices: &[u16], size_px: f32) -> Vec<Option<RasterizedGlyph>> {
+        match CpuFontRasterizer::parse(&self.bytes) {
+            Some(rasterizer) => rasterizer.rasterize_batch(glyph_indices, size_px),
+            None => glyph_indices.iter().map(|_| None).collect(),
+        }
+    }
+}
@@ -0,0 +1,314 @@
+//! Alternative, pure-Rust glyph rasterization backend.
+//!
+//! `font` rasterizes glyphs through the `stb_truetype` / WebRender
+//! native-font stack by default. That pulls in a C dependency chain that
+//! not every build wants - in particular headless rendering, deterministic
+//! text snapshot tests, and minimal-footprint builds are all better served
+//! by a pure-Rust rasterizer. This module adds exactly that, gated behind
+//! `feature = "cpu-font"`: glyph outlines are parsed with `ttf-parser` and
+//! rasterized to grayscale coverage bitmaps on the CPU (optionally using
+//! `rayon` to rasterize multiple glyphs in parallel). The bitmaps are handed
+//! back to the caller (`text_layout` keeps them in `LaidOutText`);
+//! `compositor` does not upload them, since its `Renderer::upload_texture`
+//! path takes RGBA8 only. Picking this backend is just a Cargo feature
+//! toggle - `font`'s loading and glyph lookup API does not change.
+
+#![cfg(feature = "cpu-font")]
+
+use ttf_parser::{Face, OutlineBuilder};
+
+/// A rasterized glyph: a single-channel (alpha / coverage) bitmap plus the
+/// metrics needed to place it relative to the pen position.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RasterizedGlyph {
+    /// Coverage bitmap, one byte per pixel, row-major, top-to-bottom
+    pub coverage: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+    /// Offset from the pen position to the top-left of `coverage`
+    pub left: i32,
+    pub top: i32,
+}
+
+/// Pure-Rust, `ttf-parser`-based glyph rasterizer. Unlike the default
+/// `stb_truetype` backend this has no C dependencies, which makes builds
+/// smaller and output bit-for-bit reproducible across platforms - useful
+/// for golden-image text rendering tests.
+pub struct CpuFontRasterizer<'a> {
+    face: Face<'a>,
+}
+
+impl<'a> CpuFontRasterizer<'a> {
+    /// Parses a font from raw bytes. Mirrors `font::Font::load` so swapping
+    /// backends doesn't change how callers obtain a font in the first place.
+    pub fn parse(font_bytes: &'a [u8]) -> Option<Self> {
+        Face::from_slice(font_bytes, 0).ok().map(|face| CpuFontRasterizer { face })
+    }
+
+    /// Rasterizes a single glyph at the given pixel size.
+    pub fn rasterize(&self, glyph_index: u16, size_px: f32) -> Option<RasterizedGlyph> {
+        let units_per_em = self.face.units_per_em()? as f32;
+        let scale = size_px / units_per_em;
+
+        let bbox = self.face.glyph_bounding_box(ttf_parser::GlyphId(glyph_index))?;
+        let width = ((bbox.x_max - bbox.x_min) as f32 * scale).ceil().max(0.0) as u32;
+        let height = ((bbox.y_max - bbox.y_min) as f32 * scale).ceil().max(0.0) as u32;
+
+        let mut coverage = vec![0u8; (width * height) as usize];
+        rasterize_outline_into(&self.face, glyph_index, scale, width, height, &mut coverage);
+
+        Some(RasterizedGlyph {
+            coverage,
+            width,
+            height,
+            left: (bbox.x_min as f32 * scale).floor() as i32,
+            top: (bbox.y_max as f32 * scale).ceil() as i32,
+        })
+    }
+
+    /// Rasterizes many glyphs in parallel via `rayon`. Intended for warming
+    /// up a glyph atlas in bulk (e.g. an entire font's Latin-1 range)
+    /// rather than for on-demand single-glyph lookups.
+    pub fn rasterize_batch(&self, glyph_indices: &[u16], size_px: f32) -> Vec<Option<RasterizedGlyph>> {
+        use rayon::prelude::*;
+        glyph_indices.par_iter().map(|&g| self.rasterize(g, size_px)).collect()
+    }
+}
+
+/// Scanline coverage rasterization of a glyph's outline into `out`.
+///
+/// Walks the outline via `ttf_parser`'s [`OutlineBuilder`] callbacks,
+/// flattening quadratic/cubic segments into short line segments, and
+/// accumulates each line's *signed area* contribution per pixel (the
+/// `font-rs` / `stb_truetype` technique) rather than a binary scan-convert -
+/// that's what gives the edges their anti-aliasing. The accumulation buffer
+/// is then integrated (running sum) along each scanline and converted to an
+/// unsigned coverage byte.
+fn rasterize_outline_into(face: &Face, glyph_index: u16, scale: f32, width: u32, height: u32, out: &mut [u8]) {
+    if width == 0 || height == 0 {
+        return;
+    }
+
+    let bbox = match face.glyph_bounding_box(ttf_parser::GlyphId(glyph_index)) {
+        Some(bbox) => bbox,
+        None => return,
+    };
+
+    // Transform from font units (y-up) to device pixels (y-down), with the
+    // glyph's top-left bearing as the origin.
+    let to_device = move |x: f32, y: f32| -> (f32, f32) {
+        (
+            (x - bbox.x_min as f32) * scale,
+            (bbox.y_max as f32 - y) * scale,
+        )
+    };
+
+    let mut accumulator = Accumulator::new(width as usize, height as usize);
+    {
+        let mut outliner = Outliner { to_device, acc: &mut accumulator, start: None, current: None };
+        face.outline_glyph(ttf_parser::GlyphId(glyph_index), &mut outliner);
+    }
+
+    accumulator.integrate_into(out);
+}
+
+/// Per-pixel signed-area accumulation buffer for one glyph, plus the
+/// integration step that turns it into coverage. One extra column is kept
+/// on the right so edge contributions that land exactly on the last column
+/// don't need a bounds check.
+struct Accumulator {
+    width: usize,
+    height: usize,
+    area: Vec<f32>,
+}
+
+impl Accumulator {
+    fn new(width: usize, height: usize) -> Self {
+        Self { width, height, area: vec![0.0; (width + 1) * height.max(1)] }
+    }
+
+    /// Adds the signed-area contribution of the line segment `p0 -> p1`
+    /// (already in device pixel space) to the accumulation buffer.
+    ///
+    /// This is the `font-rs` / Raph Levien scanline-coverage algorithm: each
+    /// row a line segment crosses contributes either a single "rise between
+    /// two columns" split (the `x1i <= x0i + 1` branch) or a full trapezoid
+    /// spanning several columns, written once per affected cell. `x0`/`x1`
+    /// are clamped into `0..=width` *before* being floored/ceiled into cell
+    /// indices, so an edge that lands exactly on the right border can never
+    /// produce an index of `width + 1` and walk off the end of `area`
+    /// (which is sized to hold indices `0..=width` per row, i.e. `width + 1`
+    /// cells) or, on the last row, past the end of the buffer entirely.
+    fn draw_line(&mut self, p0: (f32, f32), p1: (f32, f32)) {
+        if p0.1 == p1.1 {
+            // Horizontal edges contribute zero area and would divide by
+            // zero below.
+            return;
+        }
+
+        let (dir, p0, p1) = if p0.1 < p1.1 { (1.0f32, p0, p1) } else { (-1.0f32, p1, p0) };
+        let dxdy = (p1.0 - p0.0) / (p1.1 - p0.1);
+
+        let mut x = p0.0;
+        let y_start = p0.1.max(0.0);
+        if p0.1 < 0.0 {
+            x -= p0.1 * dxdy;
+        }
+        let y_end = p1.1.min(self.height as f32);
+        if y_start >= y_end {
+            return;
+        }
+
+        let width_f = self.width as f32;
+
+        for y in (y_start as usize)..(y_end.ceil() as usize).min(self.height) {
+            let row_top = (y as f32).max(p0.1);
+            let row_bottom = ((y + 1) as f32).min(p1.1);
+            let dy = row_bottom - row_top;
+            if dy <= 0.0 {
+                continue;
+            }
+            let x_next = x + dxdy * dy;
+            let d = dy * dir;
+
+            let (x0, x1) = if x < x_next { (x, x_next) } else { (x_next, x) };
+            // Clamp *both* ends into `[0, width]` - `x1` landing exactly on
+            // `width` is the common case (a glyph edge touching the right
+            // side of its bounding box), and without also clamping `x0` the
+            // `x0i = width` case below indexes one past this row's cells.
+            let x0 = x0.max(0.0).min(width_f);
+            let x1 = x1.min(width_f).max(0.0);
+            let row = y * (self.width + 1);
+
+            let x0_floor = x0.floor();
+            let x0i = (x0_floor as usize).min(self.width);
+            let x1_ceil = x1.ceil();
+            let x1i = (x1_ceil as usize).min(self.width);
+
+            if x1i <= x0i + 1 {
+                // The edge stays within a single pixel column this row;
+                // split its area between that column and the next.
+                let xmf = 0.5 * (x + x_next) - x0_floor;
+                self.area[row + x0i] += d * (1.0 - xmf);
+                self.area[row + (x0i + 1).min(self.width)] += d * xmf;
+            } else {
+                // The edge spans multiple columns this row: the first column
+                // gets a partial-area triangle, the columns after it ramp up
+                // by the per-column share, the last column of the span takes
+                // whatever is left of `d`, and the very last cell gets the
+                // sliver to the right of `x1`.
+                let s = (x1 - x0).recip();
+                let x0f = x0 - x0_floor;
+                let a0 = s * (1.0 - x0f) * (1.0 - x0f) * 0.5;
+                self.area[row + x0i] += d * a0;
+
+                let x1f = x1 - (x1_ceil - 1.0);
+                let a1 = s * x1f * x1f * 0.5;
+
+                let mut covered = a0;
+                for xi in (x0i + 1)..(x1i - 1) {
+                    let share = if xi == x0i + 1 { s * (1.5 - x0f) - a0 } else { s };
+                    self.area[row + xi] += d * share;
+                    covered += share;
+                }
+                self.area[row + x1i - 1] += d * (1.0 - covered - a1);
+                self.area[row + x1i] += d * a1;
+            }
+
+            x = x_next;
+        }
+    }
+
+    /// Integrates the accumulated signed area along each scanline into a
+    /// running coverage sum, clamps it to `0.0..=1.0` and writes it out as
+    /// a coverage byte.
+    fn integrate_into(&self, out: &mut [u8]) {
+        for y in 0..self.height {
+            let row = y * (self.width + 1);
+            let mut acc = 0.0f32;
+            for x in 0..self.width {
+                acc += self.area[row + x];
+                out[y * self.width + x] = (acc.abs().min(1.0) * 255.0) as u8;
+            }
+        }
+    }
+}
+
+/// Bridges `ttf_parser`'s outline callbacks (cubic/quadratic Beziers, in
+/// font-unit space) into straight line segments in device-pixel space fed
+/// to the [`Accumulator`].
+struct Outliner<'a, F: Fn(f32, f32) -> (f32, f32)> {
+    to_device: F,
+    acc: &'a mut Accumulator,
+    start: Option<(f32, f32)>,
+    current: Option<(f32, f32)>,
+}
+
+impl<'a, F: Fn(f32, f32) -> (f32, f32)> Outliner<'a, F> {
+    fn line_segment(&mut self, to: (f32, f32)) {
+        if let Some(from) = self.current {
+            self.acc.draw_line(from, to);
+        }
+        self.current = Some(to);
+    }
+
+    /// Flattens a curve into straight segments by sampling it at fixed
+    /// parametric steps - plenty for glyph sizes, and much simpler than
+    /// adaptive subdivision.
+    fn flatten<C: Fn(f32) -> (f32, f32)>(&mut self, curve: C) {
+        const STEPS: usize = 8;
+        for i in 1..=STEPS {
+            let t = i as f32 / STEPS as f32;
+            self.line_segment(curve(t));
+        }
+    }
+}
+
+impl<'a, F: Fn(f32, f32) -> (f32, f32)> OutlineBuilder for Outliner<'a, F> {
+    fn move_to(&mut self, x: f32, y: f32) {
+        let p = (self.to_device)(x, y);
+        self.start = Some(p);
+        self.current = Some(p);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let p = (self.to_device)(x, y);
+        self.line_segment(p);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let p0 = self.current.unwrap_or((x1, y1));
+        let p1 = (self.to_device)(x1, y1);
+        let p2 = (self.to_device)(x, y);
+        self.flatten(|t| {
+            let mt = 1.0 - t;
+            (
+                mt * mt * p0.0 + 2.0 * mt * t * p1.0 + t * t * p2.0,
+                mt * mt * p0.1 + 2.0 * mt * t * p1.1 + t * t * p2.1,
+            )
+        });
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let p0 = self.current.unwrap_or((x1, y1));
+        let p1 = (self.to_device)(x1, y1);
+        let p2 = (self.to_device)(x2, y2);
+        let p3 = (self.to_device)(x, y);
+        self.flatten(|t| {
+            let mt = 1.0 - t;
+            (
+                mt * mt * mt * p0.0 + 3.0 * mt * mt * t * p1.0 + 3.0 * mt * t * t * p2.0 + t * t * t * p3.0,
+                mt * mt * mt * p0.1 + 3.0 * mt * mt * t * p1.1 + 3.0 * mt * t * t * p2.1 + t * t * t * p3.1,
+            )
+        });
+    }
+
+    fn close(&mut self) {
+        if let (Some(start), Some(current)) = (self.start, self.current) {
+            if start != current {
+                self.line_segment(start);
+            }
+        }
+        self.current = self.start;
+    }
+}
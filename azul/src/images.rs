@@ -0,0 +1,78 @@
+//! Image handling - identifiers and the different ways image data can enter
+//! `app_resources` (an embedded byte slice still needing decode, a file on
+//! disk, or pixels that are already decoded, e.g. from the clipboard).
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static NEXT_IMAGE_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Handle to an image registered with `app_resources`, stable for the
+/// lifetime of the `AppResources` it was registered with.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ImageId(pub(crate) usize);
+
+impl ImageId {
+    /// Allocates a fresh, process-wide unique id.
+    pub fn new() -> Self {
+        ImageId(NEXT_IMAGE_ID.fetch_add(1, Ordering::SeqCst))
+    }
+}
+
+/// Pixel layout of a [`RawImage`]'s buffer.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RawImageFormat {
+    RGB8,
+    RGBA8,
+}
+
+impl RawImageFormat {
+    pub fn bytes_per_pixel(self) -> usize {
+        match self {
+            RawImageFormat::RGB8 => 3,
+            RawImageFormat::RGBA8 => 4,
+        }
+    }
+}
+
+/// An already-decoded image, handed to `app_resources` as-is (no file I/O,
+/// no format sniffing) - the path a pasted clipboard image or a rendered
+/// node snapshot takes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RawImage {
+    pub pixels: Vec<u8>,
+    pub width: usize,
+    pub height: usize,
+    pub data_format: RawImageFormat,
+}
+
+/// Where an image's bytes come from, and therefore how `app_resources`
+/// needs to get from this to pixels on the GPU.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImageSource {
+    /// Bytes of an encoded image (PNG, JPEG, ...) embedded in the binary
+    Embedded(&'static [u8]),
+    /// Path to an encoded image file on disk
+    File(::std::path::PathBuf),
+    /// Already-decoded pixels - nothing left to do but upload them
+    Raw(RawImage),
+}
+
+/// Lightweight copy of an `ImageSource`'s kind, without the payload -
+/// useful for widgets that want to match on "is this decoded yet" without
+/// owning the (potentially large) image data.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ImageType {
+    Embedded,
+    File,
+    Raw,
+}
+
+impl<'a> From<&'a ImageSource> for ImageType {
+    fn from(source: &'a ImageSource) -> Self {
+        match source {
+            ImageSource::Embedded(_) => ImageType::Embedded,
+            ImageSource::File(_) => ImageType::File,
+            ImageSource::Raw(_) => ImageType::Raw,
+        }
+    }
+}
@@ -86,6 +86,20 @@ pub(crate) use azul_dependencies::tinyfiledialogs;
 pub(crate) use azul_dependencies::clipboard2;
 pub(crate) use azul_dependencies::font_loader;
 
+// Clipboard image support pulls in a platform-specific crate on top of the
+// always-on, text-only `clipboard2` - gated behind its own feature (off by
+// default) rather than being unconditional on `target_os`, since none of
+// these are real dependencies of the default build until `azul_dependencies`
+// actually vendors them.
+#[cfg(all(target_os = "linux", feature = "clipboard_image"))]
+pub(crate) use azul_dependencies::x11_clipboard;
+#[cfg(all(target_os = "windows", feature = "clipboard_image"))]
+pub(crate) use azul_dependencies::clipboard_win;
+#[cfg(all(target_os = "macos", feature = "clipboard_image"))]
+pub(crate) use azul_dependencies::cocoa;
+#[cfg(all(target_os = "macos", feature = "clipboard_image"))]
+pub(crate) use azul_dependencies::objc;
+
 #[cfg(feature = "logging")]
 pub(crate) use azul_dependencies::log;
 #[cfg(feature = "svg")]
@@ -110,6 +124,12 @@ pub(crate) use azul_dependencies::twox_hash;
 extern crate azul_css;
 extern crate azul_native_style;
 extern crate azul_css_parser;
+#[cfg(feature = "cpu-font")]
+extern crate ttf_parser;
+#[cfg(feature = "cpu-font")]
+extern crate rayon;
+#[cfg(feature = "harfbuzz")]
+extern crate harfbuzz_rs;
 
 #[macro_use]
 mod macros;
@@ -128,24 +148,40 @@ pub mod daemon;
 pub mod default_callbacks;
 /// Bindings to the native file-chooser, color picker, etc. dialogs
 pub mod dialogs;
+/// Cross-platform clipboard access, including images
+pub mod clipboard;
 /// DOM / HTML node handling
 pub mod dom;
 /// Re-exports of errors
 pub mod error;
 /// Font handling
 pub mod font;
+/// Alternative pure-Rust glyph rasterizer (`ttf-parser` + CPU coverage), behind `feature = "cpu-font"`
+#[cfg(feature = "cpu-font")]
+pub mod font_rasterizer;
+/// GPU timer-query profiling of the compositor and display list
+pub mod profiler;
+/// Renderer-agnostic backend trait, with WebRender as the default implementation
+pub mod renderer;
 /// Async IO / task system
 pub mod task;
 /// Module for caching long texts (including their layout / character positions) across multiple frames
 pub mod text_cache;
 /// Text layout helper functions - useful for text layout outside of standard containers
 pub mod text_layout;
+/// Pluggable text-shaping backends (ligatures, bidi, complex scripts) used by `text_layout`
+pub mod text_shaping;
 /// The layout traits for creating a layout-able application
 pub mod traits;
 /// Built-in widgets
 pub mod widgets;
 /// Window handling
 pub mod window;
+/// Wayland windowing backend, including live HiDPI scale-factor changes
+#[cfg(feature = "wayland")]
+pub mod wayland;
+/// Transparent and decorationless window support for `WindowCreateOptions`
+pub mod window_transparency;
 /// Window state handling, event filtering
 pub mod window_state;
 /// DOM styling module
@@ -198,7 +234,11 @@ pub mod prelude {
                      MouseMode, UpdateBehaviour, UpdateMode, HidpiAdjustedBounds,
                      WindowMonitorTarget, RendererType, WindowEvent, WindowInfo, ReadOnlyWindow};
     pub use window_state::{WindowState, KeyboardState, MouseState, DebugState};
+    #[cfg(feature = "wayland")]
+    pub use wayland::{ScaleFactorChange, WaylandScaleFactorListener};
+    pub use window_transparency::{Decorations, FramebufferAlphaMode, ClearColorMode};
     pub use images::{ImageType, ImageId};
+    pub use clipboard::{Clipboard, ClipboardImage, ClipboardError};
     pub use text_cache::{TextCache, TextId};
     pub use glium::glutin::{
         dpi::{LogicalPosition, LogicalSize, PhysicalPosition, PhysicalSize},
@@ -208,8 +248,12 @@ pub mod prelude {
     pub use rusttype::Font;
     pub use app_resources::AppResources;
     pub use daemon::{TerminateDaemon, DaemonId, DaemonCallback, Daemon};
+    pub use profiler::{Profiler, ProfilerScope, ScopeTiming};
+    pub use renderer::{Renderer, RendererTextureId};
+    pub use compositor::UserGlTexture;
     pub use default_callbacks::StackCheckedPointer;
     pub use text_layout::TextLayoutOptions;
+    pub use text_shaping::{TextShaper, TextShaperKind, ShapedTextRun, ShapedGlyph};
 
     #[cfg(any(feature = "css-parser", feature = "native-style"))]
     pub use css;
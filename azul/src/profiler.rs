@@ -0,0 +1,217 @@
+//! GPU timer-query based profiling for the compositor and display list pipeline.
+//!
+//! Azul redraws the whole UI every frame, so knowing where the per-frame GPU
+//! time actually goes (building the UI texture, compositing user OpenGL
+//! textures, etc.) is important when diagnosing layout / compositing
+//! regressions. This module wraps OpenGL timer queries (`GL_TIMESTAMP`) in a
+//! small RAII guard, [`ProfilerScope`], that issues the opening query on
+//! `begin_scope` and the closing query on `drop`. Nested scopes are kept as a tree,
+//! so a frame produces a hierarchy such as
+//! `composite -> ui_texture -> user_gl_textures`.
+//!
+//! GPU queries resolve asynchronously, so reading one back too early would
+//! stall the CPU waiting on the GPU. To avoid that, the profiler keeps a
+//! ring of [`RING_SIZE`] per-frame query sets and only reads back the oldest
+//! set once enough frames have passed that the driver is guaranteed to be
+//! done with it.
+
+use std::{cell::RefCell, rc::Rc, time::Instant};
+use gleam::gl::{self, Gl};
+
+/// Number of frames' worth of timer queries to keep in flight before the
+/// oldest one is read back. Queries resolve asynchronously on the GPU, so
+/// reading back the current frame's queries immediately would stall the CPU.
+const RING_SIZE: usize = 4;
+
+/// A resolved timing for a single profiler scope, ready to be displayed in
+/// an overlay or written to a trace file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScopeTiming {
+    /// Name of the scope, e.g. `"composite"`, `"ui_texture"`, `"user_gl_textures"`
+    pub label: &'static str,
+    /// Nesting depth of this scope in the frame's scope tree (root = 0)
+    pub depth: usize,
+    /// GPU time spent inside this scope, in milliseconds
+    pub gpu_ms: f32,
+    /// Wall-clock CPU time spent inside this scope, in milliseconds
+    pub cpu_ms: f32,
+}
+
+/// A scope that has been closed (both GL queries issued); its result may or
+/// may not have resolved yet, depending on how many frames have passed.
+struct ClosedScope {
+    label: &'static str,
+    depth: usize,
+    start_query: u32,
+    end_query: u32,
+    cpu_ms: f32,
+}
+
+#[derive(Default)]
+struct FrameQueries {
+    closed: Vec<ClosedScope>,
+}
+
+struct Inner {
+    gl: Rc<dyn Gl>,
+    frames: Vec<FrameQueries>,
+    current_frame: usize,
+    open_depth: usize,
+    enabled: bool,
+}
+
+/// GPU timer-query profiler for the compositor / display list.
+///
+/// Owns a small ring of per-frame query sets so that reading timings back
+/// never stalls the renderer waiting on the GPU. Disabled by default, since
+/// the timer queries themselves have a (small) GPU cost.
+#[derive(Clone)]
+pub struct Profiler {
+    inner: Rc<RefCell<Inner>>,
+}
+
+impl Profiler {
+    /// Creates a new profiler bound to the given GL context. Profiling
+    /// starts out disabled; call [`Profiler::set_enabled`] to turn it on.
+    pub fn new(gl: Rc<dyn Gl>) -> Self {
+        Self {
+            inner: Rc::new(RefCell::new(Inner {
+                gl,
+                frames: (0..RING_SIZE).map(|_| FrameQueries::default()).collect(),
+                current_frame: 0,
+                open_depth: 0,
+                enabled: false,
+            })),
+        }
+    }
+
+    /// Enables or disables profiling. Toggling this mid-frame is not
+    /// supported; call it between frames.
+    pub fn set_enabled(&self, enabled: bool) {
+        self.inner.borrow_mut().enabled = enabled;
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.inner.borrow().enabled
+    }
+
+    /// Starts a new frame, advancing the query ring and returning the
+    /// resolved timings of whichever older frame is now guaranteed to have
+    /// finished on the GPU (empty if profiling is disabled).
+    pub fn begin_frame(&self) -> Vec<ScopeTiming> {
+        let mut inner = self.inner.borrow_mut();
+        if !inner.enabled {
+            return Vec::new();
+        }
+
+        debug_assert_eq!(inner.open_depth, 0, "profiler scope left open across a frame boundary");
+
+        inner.current_frame = (inner.current_frame + 1) % RING_SIZE;
+        let slot = inner.current_frame;
+        let resolved = resolve_frame(&*inner, slot);
+        inner.frames[slot] = FrameQueries::default();
+        resolved
+    }
+
+    /// Opens a named, nested profiler scope, issuing the opening GPU
+    /// timestamp query immediately. The returned guard issues the closing
+    /// query and records the scope when it is dropped, so wrapping a block
+    /// of draw calls is just `let _scope = profiler.begin_scope("composite");`.
+    pub fn begin_scope(&self, label: &'static str) -> ProfilerScope {
+        let mut inner = self.inner.borrow_mut();
+        if !inner.enabled {
+            return ProfilerScope { profiler: None, label, start_query: 0, end_query: 0, depth: 0, cpu_start: Instant::now() };
+        }
+
+        let ids = inner.gl.gen_queries(2);
+        let (start_query, end_query) = (ids[0], ids[1]);
+        inner.gl.query_counter(start_query, gl::TIMESTAMP);
+
+        let depth = inner.open_depth;
+        inner.open_depth += 1;
+
+        ProfilerScope {
+            profiler: Some(self.clone()),
+            label,
+            start_query,
+            end_query,
+            depth,
+            cpu_start: Instant::now(),
+        }
+    }
+}
+
+fn resolve_frame(inner: &Inner, slot: usize) -> Vec<ScopeTiming> {
+    let closed = &inner.frames[slot].closed;
+    let mut timings = Vec::with_capacity(closed.len());
+    let mut to_delete = Vec::with_capacity(closed.len() * 2);
+
+    for scope in closed {
+        to_delete.push(scope.start_query);
+        to_delete.push(scope.end_query);
+
+        let available = inner.gl.get_query_object_iv(scope.end_query, gl::QUERY_RESULT_AVAILABLE);
+        if available == 0 {
+            // Not resolved yet (shouldn't normally happen with `RING_SIZE`
+            // frames of slack) - skip rather than stall on `get_query_object`.
+            continue;
+        }
+
+        let start_ns = inner.gl.get_query_object_ui64v(scope.start_query, gl::QUERY_RESULT);
+        let end_ns = inner.gl.get_query_object_ui64v(scope.end_query, gl::QUERY_RESULT);
+        let gpu_ms = end_ns.saturating_sub(start_ns) as f32 / 1_000_000.0;
+
+        timings.push(ScopeTiming {
+            label: scope.label,
+            depth: scope.depth,
+            gpu_ms,
+            cpu_ms: scope.cpu_ms,
+        });
+    }
+
+    // This slot is about to be overwritten by `begin_frame` regardless of
+    // whether every scope resolved in time, so free the GL query objects
+    // now instead of leaking two per scope every frame. Deleting a query
+    // that hasn't signalled yet is legal - the driver just defers the
+    // actual free until it completes.
+    if !to_delete.is_empty() {
+        inner.gl.delete_queries(&to_delete);
+    }
+
+    timings
+}
+
+/// RAII guard returned by [`Profiler::begin_scope`]. Closing the scope (and
+/// issuing the matching GPU timestamp query) happens automatically on drop,
+/// so scopes nest naturally with the enclosing Rust block structure.
+pub struct ProfilerScope {
+    profiler: Option<Profiler>,
+    label: &'static str,
+    start_query: u32,
+    end_query: u32,
+    depth: usize,
+    cpu_start: Instant,
+}
+
+impl Drop for ProfilerScope {
+    fn drop(&mut self) {
+        let profiler = match self.profiler.take() {
+            Some(p) => p,
+            None => return,
+        };
+
+        let mut inner = profiler.inner.borrow_mut();
+        inner.gl.query_counter(self.end_query, gl::TIMESTAMP);
+        inner.open_depth -= 1;
+
+        let cpu_ms = self.cpu_start.elapsed().as_secs_f64() as f32 * 1000.0;
+        let slot = inner.current_frame;
+        inner.frames[slot].closed.push(ClosedScope {
+            label: self.label,
+            depth: self.depth,
+            start_query: self.start_query,
+            end_query: self.end_query,
+            cpu_ms,
+        });
+    }
+}
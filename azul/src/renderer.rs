@@ -0,0 +1,231 @@
+//! Abstraction over the graphics backend that actually puts pixels on screen.
+//!
+//! `display_list` and `compositor` are written against whatever backend is
+//! uploading textures and presenting frames; historically that has meant
+//! talking to `webrender` / `glium` directly. The [`Renderer`] trait pulls
+//! out the handful of operations Azul actually needs - uploading / destroying
+//! textures, submitting a built display list, presenting a frame and
+//! reacting to resize / DPI changes - so an embedder that already owns a
+//! GL (or, in principle, another) context can drive Azul without pulling in
+//! the full WebRender compositor.
+//!
+//! [`WebRenderRenderer`] is the default implementation and is what
+//! [`App::run`](crate::app::App::run) / [`Window::new`](crate::window::Window::new)
+//! use unless a different [`Renderer`] is explicitly supplied; it is gated
+//! behind the same `webrender` dependency the rest of the crate already uses.
+
+use azul_css::ColorU;
+use window::HidpiAdjustedBounds;
+use images::ImageId;
+use FastHashMap;
+
+/// Opaque handle to a texture uploaded through a [`Renderer`]. Backends are
+/// free to interpret this however they like internally (a WebRender
+/// `ImageKey`, a raw GL texture name, ...); callers only ever pass it back
+/// to the same `Renderer` that produced it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct RendererTextureId(pub u64);
+
+/// A display list that has already been built by `display_list` and is
+/// ready to be handed to the backend for rasterization / compositing.
+///
+/// This is intentionally opaque at the `Renderer` boundary: what a "built
+/// display list" looks like is a backend concern (a WebRender
+/// `BuiltDisplayList`, or something else entirely), so the trait only deals
+/// with a handle to one.
+pub struct BuiltDisplayListHandle(pub(crate) Box<dyn ::std::any::Any>);
+
+/// Renderer-agnostic backend for uploading resources and producing frames.
+///
+/// Implementations are expected to be cheap to hand out handles from (the
+/// actual GPU resources live behind them) and are driven once per frame by
+/// the compositor: textures are uploaded/destroyed as the DOM's `image`/`gl
+/// texture` nodes change, a display list is submitted, and the frame is
+/// presented.
+pub trait Renderer {
+    /// Uploads an RGBA8 texture (e.g. a decoded image, or a user-rendered
+    /// OpenGL texture) and returns a handle the compositor can reference
+    /// from a display list.
+    fn upload_texture(&mut self, width: u32, height: u32, pixels: &[u8]) -> RendererTextureId;
+
+    /// Destroys a previously uploaded texture. Called once the DOM no
+    /// longer references the corresponding `ImageId` / GL texture.
+    fn destroy_texture(&mut self, texture: RendererTextureId);
+
+    /// Submits a built display list for the next frame. Backends may defer
+    /// actual GPU work until [`Renderer::present`].
+    fn submit_display_list(&mut self, display_list: BuiltDisplayListHandle, clear_color: ColorU);
+
+    /// Presents the most recently submitted frame (swaps buffers / flips).
+    fn present(&mut self);
+
+    /// Called when the window is resized or its HiDPI scale factor changes,
+    /// so the backend can resize its framebuffer and rescale any cached
+    /// backend-side textures (e.g. a glyph atlas) to match.
+    fn resize(&mut self, bounds: HidpiAdjustedBounds);
+
+    /// Looks up the backend texture for an already-decoded image resource,
+    /// if the renderer keeps its own image cache (most do, to avoid
+    /// re-uploading on every frame).
+    fn texture_for_image(&self, image: ImageId) -> Option<RendererTextureId>;
+
+    /// Records that `texture` now backs `image`, so a later
+    /// `texture_for_image(image)` finds it without `compositor` having to
+    /// re-upload. Called by `compositor` right after `upload_texture` for
+    /// any texture it uploaded on behalf of an `ImageId` (an
+    /// `app_resources`-owned image or a `UserGlTexture`). The default no-op is
+    /// fine for a backend that doesn't keep an image cache of its own and
+    /// always re-uploads.
+    fn note_image(&mut self, _image: ImageId, _texture: RendererTextureId) {}
+}
+
+/// The default [`Renderer`] implementation, backed by WebRender + glium.
+/// This is what `App` / `Window` use unless a different backend is
+/// supplied; it owns the same WebRender `Renderer`, `RenderApi` and
+/// `DocumentId` that the compositor has always driven directly, so that
+/// driving it through the trait is a drop-in replacement rather than a
+/// partial one.
+#[cfg(feature = "webrender")]
+pub struct WebRenderRenderer {
+    pub(crate) internal: ::webrender::Renderer,
+    render_api: ::webrender::api::RenderApi,
+    document_id: ::webrender::api::DocumentId,
+    pipeline_id: ::webrender::api::PipelineId,
+    epoch: ::webrender::api::Epoch,
+    device_size: ::webrender::api::units::DeviceIntSize,
+    /// Handles handed out by [`Renderer::upload_texture`], keyed to the
+    /// WebRender `ImageKey` that actually owns the GPU-side data.
+    textures: FastHashMap<RendererTextureId, ::webrender::api::ImageKey>,
+    /// Backend texture cache keyed by the `ImageId` it was uploaded for, so
+    /// repeated frames referencing the same decoded image don't need to
+    /// re-upload; populated via [`Renderer::note_image`], which `compositor`
+    /// calls from `Compositor::ensure_uploaded`/`upload_image`.
+    image_cache: FastHashMap<ImageId, RendererTextureId>,
+    next_texture_id: u64,
+}
+
+#[cfg(feature = "webrender")]
+impl WebRenderRenderer {
+    /// Builds the default renderer around an already-initialized WebRender
+    /// `Renderer` + `RenderApiSender`, the way `compositor` has always set
+    /// WebRender up - this just gives that setup a name that can be stored
+    /// behind the [`Renderer`] trait instead of only behind `WebRenderRenderer`.
+    pub(crate) fn new(
+        internal: ::webrender::Renderer,
+        render_api_sender: ::webrender::api::RenderApiSender,
+        device_size: ::webrender::api::units::DeviceIntSize,
+    ) -> Self {
+        let render_api = render_api_sender.create_api();
+        let document_id = render_api.add_document(device_size, 0);
+        Self {
+            internal,
+            render_api,
+            document_id,
+            pipeline_id: ::webrender::api::PipelineId(0, 0),
+            epoch: ::webrender::api::Epoch(0),
+            device_size,
+            textures: FastHashMap::default(),
+            image_cache: FastHashMap::default(),
+            next_texture_id: 0,
+        }
+    }
+}
+
+#[cfg(feature = "webrender")]
+impl Renderer for WebRenderRenderer {
+    fn upload_texture(&mut self, width: u32, height: u32, pixels: &[u8]) -> RendererTextureId {
+        let key = self.render_api.generate_image_key();
+        let descriptor = ::webrender::api::ImageDescriptor::new(
+            width as i32,
+            height as i32,
+            ::webrender::api::ImageFormat::RGBA8,
+            ::webrender::api::ImageDescriptorFlags::empty(),
+        );
+
+        let mut txn = ::webrender::api::Transaction::new();
+        txn.add_image(key, descriptor, ::webrender::api::ImageData::new(pixels.to_vec()), None);
+        self.render_api.send_transaction(self.document_id, txn);
+
+        let id = RendererTextureId(self.next_texture_id);
+        self.next_texture_id += 1;
+        self.textures.insert(id, key);
+        id
+    }
+
+    fn destroy_texture(&mut self, texture: RendererTextureId) {
+        let key = match self.textures.remove(&texture) {
+            Some(key) => key,
+            None => return,
+        };
+        self.image_cache.retain(|_, t| *t != texture);
+
+        let mut txn = ::webrender::api::Transaction::new();
+        txn.delete_image(key);
+        self.render_api.send_transaction(self.document_id, txn);
+    }
+
+    fn submit_display_list(&mut self, display_list: BuiltDisplayListHandle, clear_color: ColorU) {
+        let built = display_list.0
+            .downcast::<::webrender::api::BuiltDisplayList>()
+            .expect("BuiltDisplayListHandle did not come from the WebRender backend");
+
+        self.epoch.0 = self.epoch.0.wrapping_add(1);
+        let content_size = self.device_size.to_f32();
+
+        let mut txn = ::webrender::api::Transaction::new();
+        txn.set_display_list(
+            self.epoch,
+            Some(to_webrender_color(clear_color)),
+            content_size,
+            (self.pipeline_id, content_size, *built),
+            true,
+        );
+        txn.set_root_pipeline(self.pipeline_id);
+        txn.generate_frame();
+        self.render_api.send_transaction(self.document_id, txn);
+    }
+
+    fn present(&mut self) {
+        self.internal.update();
+        self.internal.render(self.device_size)
+            .expect("WebRender frame render failed");
+        let _ = self.internal.flush_pipeline_info();
+    }
+
+    fn resize(&mut self, bounds: HidpiAdjustedBounds) {
+        self.device_size = ::webrender::api::units::DeviceIntSize::new(
+            bounds.physical_size.width as i32,
+            bounds.physical_size.height as i32,
+        );
+
+        let mut txn = ::webrender::api::Transaction::new();
+        txn.set_document_view(
+            ::webrender::api::units::DeviceIntRect::new(
+                ::webrender::api::units::DeviceIntPoint::zero(),
+                self.device_size,
+            ),
+            bounds.hidpi_factor,
+        );
+        self.render_api.send_transaction(self.document_id, txn);
+    }
+
+    fn texture_for_image(&self, image: ImageId) -> Option<RendererTextureId> {
+        self.image_cache.get(&image).copied()
+    }
+
+    fn note_image(&mut self, image: ImageId, texture: RendererTextureId) {
+        self.image_cache.insert(image, texture);
+    }
+}
+
+/// Converts the DOM's `azul_css::ColorU` into the premultiplied, 0.0-1.0
+/// float color WebRender's `Transaction::set_display_list` expects.
+#[cfg(feature = "webrender")]
+fn to_webrender_color(color: ColorU) -> ::webrender::api::ColorF {
+    ::webrender::api::ColorF::new(
+        color.r as f32 / 255.0,
+        color.g as f32 / 255.0,
+        color.b as f32 / 255.0,
+        color.a as f32 / 255.0,
+    )
+}
@@ -0,0 +1,78 @@
+//! Module for caching long texts (including their layout / character
+//! positions) across multiple frames.
+//!
+//! Azul redraws the whole UI every frame (see the crate-level docs), so
+//! re-shaping and re-laying-out every text node on every frame regardless of
+//! whether its content, font, size or shaper changed would be wasteful -
+//! `TextCache` is the memoization layer that makes that not happen. A cached
+//! entry's key includes the shaper that produced it
+//! ([`TextShaperKind::cache_key`]) in addition to the text/font/size it has
+//! always keyed on, so switching a node's `TextLayoutOptions::shaper` (e.g.
+//! opting into `Harfbuzz` for a paragraph that turned out to need real bidi)
+//! invalidates the old entry instead of silently reusing a layout a
+//! different engine produced.
+
+use azul_css::FontId;
+
+use font::Font;
+use text_layout::{layout_text, LaidOutText, TextLayoutOptions};
+use FastHashMap;
+
+/// Handle to a cached laid-out text, stable for the lifetime of the
+/// `TextCache` it was inserted into.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct TextId(usize);
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CacheKey {
+    text: String,
+    font_id: FontId,
+    // Stored as bits so the key can derive `Hash`/`Eq` - `f32` itself can't.
+    font_size_px_bits: u32,
+    shaper: &'static str,
+}
+
+impl CacheKey {
+    fn new(text: &str, font_id: &FontId, options: &TextLayoutOptions) -> Self {
+        Self {
+            text: text.to_owned(),
+            font_id: font_id.clone(),
+            font_size_px_bits: options.font_size_px.to_bits(),
+            shaper: options.shaper.shaper().cache_key(),
+        }
+    }
+}
+
+/// Caches laid-out text across frames, keyed on the text content, font,
+/// size and shaper that produced it.
+#[derive(Default)]
+pub struct TextCache {
+    by_key: FastHashMap<CacheKey, LaidOutText>,
+}
+
+impl TextCache {
+    pub fn new() -> Self {
+        Self { by_key: FastHashMap::default() }
+    }
+
+    /// Returns the cached layout for this exact `(text, font, size, shaper)`
+    /// combination, shaping and inserting it first if this is the first time
+    /// it's been asked for.
+    pub fn get_or_layout(
+        &mut self,
+        text: &str,
+        font: &Font,
+        font_id: &FontId,
+        options: &TextLayoutOptions,
+    ) -> &LaidOutText {
+        let key = CacheKey::new(text, font_id, options);
+        self.by_key.entry(key).or_insert_with(|| layout_text(text, font, font_id, options))
+    }
+
+    /// Drops every cached layout. Called after a HiDPI scale-factor change -
+    /// a cached layout's glyph positions are in device pixels at the old
+    /// scale factor, so none of them are valid at the new one.
+    pub fn invalidate_all(&mut self) {
+        self.by_key.clear();
+    }
+}
@@ -0,0 +1,88 @@
+//! Text layout helper functions - useful for text layout outside of standard
+//! containers.
+//!
+//! Shapes one run of text with a `TextShaper` and returns the resulting
+//! `ShapedGlyph`s plus the run's total advance. Horizontal alignment is not
+//! applied here; `total_advance` lets the caller center or right-align the
+//! run. This is deliberately the thin layer between `text_shaping` (which knows
+//! how to shape one run) and `text_cache` (which knows how to avoid
+//! reshaping a run that hasn't changed) - it does not itself do bidi
+//! paragraph segmentation, word wrapping or line breaking, none of which
+//! exist in this checkout (`ui_solver` owns that).
+
+use azul_css::{FontId, StyleTextAlignmentHorz};
+
+use font::Font;
+use text_shaping::{ShapedTextRun, TextShaperKind};
+
+#[cfg(feature = "cpu-font")]
+use font_rasterizer::RasterizedGlyph;
+
+/// Options controlling how a run of text is laid out.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TextLayoutOptions {
+    pub font_size_px: f32,
+    pub alignment: StyleTextAlignmentHorz,
+    /// Which [`TextShaper`](crate::text_shaping::TextShaper) backend to
+    /// shape this run with. Defaults to [`TextShaperKind::Rusttype`] (today's
+    /// advance-only behavior); switching this to `Harfbuzz` is the only
+    /// change needed to get ligatures / bidi-aware shaping for a given run.
+    pub shaper: TextShaperKind,
+}
+
+impl Default for TextLayoutOptions {
+    fn default() -> Self {
+        Self {
+            font_size_px: 16.0,
+            alignment: StyleTextAlignmentHorz::Left,
+            shaper: TextShaperKind::default(),
+        }
+    }
+}
+
+/// A laid-out run: the shaped glyphs plus the total advance, so a caller can
+/// place the next run (or center / right-align this one) without re-walking
+/// `glyphs`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct LaidOutText {
+    pub shaped: ShapedTextRun,
+    pub total_advance: f32,
+    /// One rasterized coverage bitmap per glyph in `shaped.glyphs`, in the
+    /// same order, produced by the pure-Rust `font_rasterizer` backend
+    /// instead of the default `stb_truetype`/WebRender native-font path.
+    /// `None` for a glyph `font_rasterizer` couldn't parse (e.g. an empty
+    /// glyph like a space). Only populated with `feature = "cpu-font"` -
+    /// without it, laying out text still works, it just has no bitmaps to
+    /// hand to a CPU-side glyph atlas.
+    #[cfg(feature = "cpu-font")]
+    pub rasterized: Vec<Option<RasterizedGlyph>>,
+}
+
+/// Shapes and positions one run of text. `font_id` is only used to build the
+/// `ShapingContext` the chosen shaper expects (and as part of `text_cache`'s
+/// cache key upstream of this call) - the actual glyph lookups go through
+/// `font`.
+pub fn layout_text(text: &str, font: &Font, font_id: &FontId, options: &TextLayoutOptions) -> LaidOutText {
+    let context = ::text_shaping::ShapingContext {
+        font_id,
+        font_size_px: options.font_size_px,
+        alignment: options.alignment,
+        font_bytes: font.bytes(),
+    };
+
+    let shaped = options.shaper.shaper().shape(text, font.rusttype_font(), &context);
+    let total_advance = shaped.glyphs.last().map(|g| g.advance_x).unwrap_or(0.0);
+
+    #[cfg(feature = "cpu-font")]
+    let rasterized = {
+        let glyph_indices: Vec<u16> = shaped.glyphs.iter().map(|g| g.glyph_index as u16).collect();
+        font.rasterize_cpu_batch(&glyph_indices, options.font_size_px)
+    };
+
+    LaidOutText {
+        shaped,
+        total_advance,
+        #[cfg(feature = "cpu-font")]
+        rasterized,
+    }
+}
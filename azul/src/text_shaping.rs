@@ -0,0 +1,233 @@
+//! Pluggable text-shaping backends.
+//!
+//! `text_layout` positions glyphs by walking a run of text and accumulating
+//! `rusttype` advance widths, which is enough for left-to-right Latin text
+//! but does not actually *shape* anything: no ligatures, no bidi reordering,
+//! no Arabic / Indic cluster formation. [`TextShaper`] pulls the "turn a run
+//! of text + font + style into positioned glyph clusters" step out into its
+//! own trait so a real shaping engine can be swapped in without touching the
+//! rest of the text layout pipeline.
+//!
+//! [`RusttypeShaper`] reproduces today's advance-based behavior and remains
+//! the default. A HarfBuzz-backed [`HarfbuzzShaper`] is available behind
+//! `feature = "harfbuzz"` for apps that need correct complex-script shaping.
+//!
+//! `TextLayoutOptions` gains a `shaper: TextShaperKind` field so callers can
+//! opt into a different backend, and `text_cache` keys its cached layouts on
+//! the shaper that produced them (in addition to the text + font + size it
+//! already keys on) so a paragraph re-laid-out with a different shaper
+//! doesn't hit a stale cache entry.
+
+use azul_css::{FontId, StyleTextAlignmentHorz};
+use rusttype::{Font, Scale};
+
+/// A single shaped glyph, positioned relative to the start of its run.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ShapedGlyph {
+    /// Index into the font's glyph table
+    pub glyph_index: u32,
+    /// Pen position after this glyph, in pixels from the start of the run
+    /// (the sum of the advances of this glyph and every glyph before it)
+    pub advance_x: f32,
+    /// Horizontal offset to apply before drawing this glyph, in pixels
+    /// (used by shapers that emit kerning / mark positioning)
+    pub offset_x: f32,
+    /// Vertical offset to apply before drawing this glyph, in pixels
+    pub offset_y: f32,
+    /// Byte offset of the source cluster this glyph belongs to, within the
+    /// original (pre-shaping) text run. Multiple glyphs may share a
+    /// cluster (ligatures) and a cluster may produce multiple glyphs
+    /// (decomposed marks), which is why this isn't simply the glyph's index.
+    pub cluster: usize,
+}
+
+/// The result of shaping one run of same-font, same-direction text.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ShapedTextRun {
+    pub glyphs: Vec<ShapedGlyph>,
+    /// Whether the run was laid out right-to-left (relevant for bidi runs)
+    pub is_rtl: bool,
+}
+
+/// Font + size + alignment context a [`TextShaper`] needs to shape a run.
+/// Mirrors the subset of `TextLayoutOptions` that actually affects shaping.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ShapingContext<'a> {
+    pub font_id: &'a FontId,
+    pub font_size_px: f32,
+    pub alignment: StyleTextAlignmentHorz,
+    /// Raw font file bytes backing `font_id`. `RusttypeShaper` doesn't need
+    /// these (it shapes directly off the loaded `rusttype::Font`), but
+    /// `HarfbuzzShaper` does, since HarfBuzz builds its own `Face` from the
+    /// table data rather than sharing `rusttype`'s parse.
+    pub font_bytes: &'a [u8],
+}
+
+/// Converts a run of text into positioned glyph clusters.
+///
+/// Implementations may assume the input run is a single font + direction;
+/// splitting a paragraph into such runs (bidi segmentation, script runs) is
+/// the caller's (`text_layout`'s) responsibility.
+pub trait TextShaper {
+    /// Shapes `text` using the given context and font, returning positioned
+    /// glyphs. `font` is the already-loaded `rusttype::Font` backing
+    /// `context.font_id`, passed in by `text_layout` - shaping
+    /// needs the actual font to look up glyph ids / metrics, not just its id.
+    fn shape(&self, text: &str, font: &Font, context: &ShapingContext) -> ShapedTextRun;
+
+    /// A short, stable identifier for this shaper, used by `text_cache` to
+    /// key cached layouts so a change of shaper invalidates old entries
+    /// instead of silently reusing a layout produced by a different engine.
+    fn cache_key(&self) -> &'static str;
+}
+
+/// Default shaper: positions glyphs purely from `rusttype` advance widths,
+/// in logical (string) order. No ligatures, no bidi, no cluster reordering -
+/// this is exactly today's `text_layout` behavior, kept as the default so
+/// existing apps see no change unless they opt into another shaper.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct RusttypeShaper;
+
+impl TextShaper for RusttypeShaper {
+    fn shape(&self, text: &str, font: &Font, context: &ShapingContext) -> ShapedTextRun {
+        let scale = Scale::uniform(context.font_size_px);
+        let mut glyphs = Vec::with_capacity(text.len());
+        let mut advance_x = 0.0;
+
+        for (cluster, ch) in text.char_indices() {
+            // `rusttype::Font::glyph` looks the character up in the font's
+            // cmap, so this is an actual glyph id rather than the Unicode
+            // codepoint; `h_metrics().advance_width` is the real hinted/
+            // unhinted advance at this scale, same as `text_layout` has
+            // always accumulated - no reordering or clustering happens here,
+            // in logical (string) order, which is exactly today's behavior.
+            let glyph = font.glyph(ch).scaled(scale);
+            let advance = glyph.h_metrics().advance_width;
+            advance_x += advance;
+
+            glyphs.push(ShapedGlyph {
+                glyph_index: glyph.id().0,
+                advance_x,
+                offset_x: 0.0,
+                offset_y: 0.0,
+                cluster,
+            });
+        }
+
+        ShapedTextRun { glyphs, is_rtl: false }
+    }
+
+    fn cache_key(&self) -> &'static str {
+        "rusttype"
+    }
+}
+
+/// HarfBuzz-backed shaper for correct complex-script shaping (ligatures,
+/// bidi-aware cluster reordering, Arabic / Indic cluster formation).
+/// Requires `feature = "harfbuzz"`.
+#[cfg(feature = "harfbuzz")]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct HarfbuzzShaper;
+
+#[cfg(feature = "harfbuzz")]
+impl TextShaper for HarfbuzzShaper {
+    fn shape(&self, text: &str, font: &Font, context: &ShapingContext) -> ShapedTextRun {
+        // `rusttype::Font` doesn't expose its raw table bytes, so the
+        // HarfBuzz face is built from the same bytes `text_layout` loaded
+        // the `rusttype::Font` from (`context.font_bytes`, kept around by
+        // `font::Font` for consumers that parse the font tables themselves).
+        let face = ::harfbuzz_rs::Face::new(context.font_bytes, 0);
+        let hb_font = ::harfbuzz_rs::Font::new(face);
+
+        // `text_layout` hands us a single run, so there's no mixed-direction
+        // reordering to do here - just pick the one direction the run's
+        // dominant script actually needs, instead of always shaping as LTR
+        // (which silently mirrors every Arabic/Hebrew run).
+        let is_rtl = dominant_direction_is_rtl(text);
+        let direction = if is_rtl { ::harfbuzz_rs::Direction::Rtl } else { ::harfbuzz_rs::Direction::Ltr };
+
+        let buffer = ::harfbuzz_rs::UnicodeBuffer::new()
+            .add_str(text)
+            .set_direction(direction);
+
+        let output = ::harfbuzz_rs::shape(&hb_font, buffer, &[]);
+        let positions = output.get_glyph_positions();
+        let infos = output.get_glyph_infos();
+
+        let scale = context.font_size_px / font.units_per_em() as f32;
+        let mut glyphs = Vec::with_capacity(infos.len());
+        let mut advance_x = 0.0;
+
+        for (info, pos) in infos.iter().zip(positions.iter()) {
+            advance_x += pos.x_advance as f32 * scale;
+
+            glyphs.push(ShapedGlyph {
+                glyph_index: info.codepoint,
+                advance_x,
+                offset_x: pos.x_offset as f32 * scale,
+                offset_y: pos.y_offset as f32 * scale,
+                cluster: info.cluster as usize,
+            });
+        }
+
+        ShapedTextRun { glyphs, is_rtl }
+    }
+
+    fn cache_key(&self) -> &'static str {
+        "harfbuzz"
+    }
+}
+
+/// A cheap, single-run direction guess: true if the first strongly-directional
+/// character in `text` is RTL, counting U+0590..=U+08FF (Hebrew, Arabic,
+/// Syriac, Thaana, ...) and the Hebrew / Arabic presentation forms as RTL
+/// and every other alphabetic character as LTR. This is not full UAX#9
+/// bidi - there's no run splitting or embedding-level resolution - but it's
+/// enough to stop every Hebrew/Arabic paragraph being shaped backwards, which
+/// is strictly better than the hardcoded `Direction::Ltr` this replaces.
+#[cfg(feature = "harfbuzz")]
+fn dominant_direction_is_rtl(text: &str) -> bool {
+    text.chars().find_map(|ch| {
+        let cp = ch as u32;
+        let is_rtl = (0x0590..=0x08FF).contains(&cp)   // Hebrew, Arabic, Syriac, Thaana, ...
+            || (0xFB1D..=0xFDFF).contains(&cp)         // Hebrew / Arabic presentation forms-A
+            || (0xFE70..=0xFEFF).contains(&cp);         // Arabic presentation forms-B
+        let is_strong_ltr = ch.is_alphabetic() && !is_rtl;
+        if is_rtl {
+            Some(true)
+        } else if is_strong_ltr {
+            Some(false)
+        } else {
+            None
+        }
+    }).unwrap_or(false)
+}
+
+/// Which [`TextShaper`] a `TextLayoutOptions` should use. Stored instead of
+/// a trait object so it's `Copy`, hashable, and cheap to use as part of a
+/// `text_cache` lookup key.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum TextShaperKind {
+    /// [`RusttypeShaper`] - advance-based layout, no real shaping (default)
+    Rusttype,
+    /// [`HarfbuzzShaper`] - full shaping, requires `feature = "harfbuzz"`
+    #[cfg(feature = "harfbuzz")]
+    Harfbuzz,
+}
+
+impl Default for TextShaperKind {
+    fn default() -> Self {
+        TextShaperKind::Rusttype
+    }
+}
+
+impl TextShaperKind {
+    /// Returns the concrete shaper for this kind.
+    pub fn shaper(self) -> &'static dyn TextShaper {
+        match self {
+            TextShaperKind::Rusttype => &RusttypeShaper,
+            #[cfg(feature = "harfbuzz")]
+            TextShaperKind::Harfbuzz => &HarfbuzzShaper,
+        }
+    }
+}
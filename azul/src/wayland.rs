@@ -0,0 +1,83 @@
+//! Wayland windowing support, including live HiDPI scale-factor changes.
+//!
+//! `window` / `window_state` have so far assumed a single, static HiDPI
+//! factor captured once at window creation, and the glutin path has
+//! defaulted toward X11. Neither holds up under Wayland: a compositor can
+//! send a scale-factor change at any time (the window moved to another
+//! monitor, a virtual/remote display connected), and without reacting to it
+//! the frame buffer, the CSS-pixel-to-physical-pixel mapping and the glyph
+//! atlas all go stale - the window keeps rendering at the old DPI until it
+//! is recreated.
+//!
+//! This module turns the compositor's scale-factor-changed notification
+//! into a first-class [`WindowEvent::ScaleFactorChanged`](crate::window::WindowEvent)
+//! so the existing event loop in `window` picks it up the same way it
+//! already handles resize: rebuild `HidpiAdjustedBounds`, trigger a reflow,
+//! and invalidate the caches that are keyed on scale factor.
+
+use glium::glutin::dpi::PhysicalSize;
+use window::{HidpiAdjustedBounds, WindowEvent};
+
+/// What needs to be invalidated / recomputed in response to a scale-factor
+/// change. Returned so the window event loop can apply each step in the
+/// same order it already does for a plain resize.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ScaleFactorChange {
+    pub old_hidpi_factor: f32,
+    pub new_hidpi_factor: f32,
+    pub new_bounds: HidpiAdjustedBounds,
+}
+
+impl ScaleFactorChange {
+    /// Whether the glyph atlas and other DPI-keyed caches need to be
+    /// rebuilt, as opposed to just re-laid-out. A change is only "real" if
+    /// the factor actually moved - some compositors resend the current
+    /// value on unrelated events.
+    pub fn needs_cache_invalidation(&self) -> bool {
+        (self.old_hidpi_factor - self.new_hidpi_factor).abs() > ::std::f32::EPSILON
+    }
+}
+
+/// Wayland-specific window backend glue.
+///
+/// Wraps the scale-factor-changed notification the Wayland compositor
+/// delivers (`wl_surface.preferred_buffer_scale` / the `xdg_output`
+/// `scale` event, depending on protocol version) and turns it into a
+/// [`ScaleFactorChange`] that the rest of `window` can act on the same way
+/// it already reacts to a resize.
+pub struct WaylandScaleFactorListener {
+    current_hidpi_factor: f32,
+}
+
+impl WaylandScaleFactorListener {
+    pub fn new(initial_hidpi_factor: f32) -> Self {
+        Self { current_hidpi_factor: initial_hidpi_factor }
+    }
+
+    /// Called from the glutin/Wayland event loop whenever the compositor
+    /// reports a new preferred scale factor for the surface. Returns the
+    /// change directly as a [`WindowEvent`] so the event loop can push it
+    /// onto the same queue it already pushes resize / redraw events onto,
+    /// rather than needing a separate Wayland-specific dispatch path.
+    pub fn on_scale_factor_changed(&mut self, new_hidpi_factor: f32, logical_size: (f64, f64)) -> WindowEvent {
+        let old_hidpi_factor = self.current_hidpi_factor;
+        self.current_hidpi_factor = new_hidpi_factor;
+
+        let physical_size = PhysicalSize::new(
+            logical_size.0 * new_hidpi_factor as f64,
+            logical_size.1 * new_hidpi_factor as f64,
+        );
+
+        ScaleFactorChange {
+            old_hidpi_factor,
+            new_hidpi_factor,
+            new_bounds: HidpiAdjustedBounds::from_bounds(physical_size, new_hidpi_factor),
+        }.into()
+    }
+}
+
+impl From<ScaleFactorChange> for WindowEvent {
+    fn from(change: ScaleFactorChange) -> Self {
+        WindowEvent::ScaleFactorChanged(change)
+    }
+}
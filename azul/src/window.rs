@@ -0,0 +1,375 @@
+//! Window handling.
+//!
+//! Owns the GL context, the [`Renderer`] backend driving it (WebRender by
+//! default, see [`RendererType`]) and the [`compositor::Compositor`] that
+//! sits on top of that backend - [`Window::render`] is what actually drives
+//! a frame through it. `WindowCreateOptions` is also where the two
+//! `window_transparency` settings live (`transparent_options`), since
+//! requesting an alpha-capable framebuffer has to happen at context
+//! creation time, before the first frame is ever composited.
+
+use std::rc::Rc;
+use azul_css::ColorU;
+use gleam::gl::Gl;
+use glium::glutin::dpi::{LogicalSize, PhysicalSize};
+
+use app_resources::AppResources;
+use compositor::{Compositor, UserGlTexture};
+use images::ImageId;
+use profiler::{Profiler, ScopeTiming};
+use renderer::{Renderer, WebRenderRenderer};
+use text_cache::TextCache;
+use window_transparency::{Decorations, FramebufferAlphaMode, WindowTransparencyOptions};
+
+#[cfg(feature = "wayland")]
+use wayland::ScaleFactorChange;
+
+/// Identifies one `Window` among the (possibly several) windows an `App` owns.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct WindowId(pub(crate) usize);
+
+/// A window's drawable area, expressed in both logical (CSS) and physical
+/// (framebuffer) pixels, plus the HiDPI factor relating the two. Passed to
+/// `Renderer::resize` and carried by [`WindowEvent::Resized`] /
+/// `ScaleFactorChanged` so a backend never has to re-derive physical size
+/// from logical size and a possibly-stale scale factor.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct HidpiAdjustedBounds {
+    pub logical_size: LogicalSize,
+    pub physical_size: PhysicalSize,
+    pub hidpi_factor: f32,
+}
+
+impl HidpiAdjustedBounds {
+    /// Derives the logical size from a physical size and HiDPI factor -
+    /// the direction a resize / scale-factor-changed event always arrives in.
+    pub fn from_bounds(physical_size: PhysicalSize, hidpi_factor: f32) -> Self {
+        let logical_size = LogicalSize::new(
+            physical_size.width / hidpi_factor as f64,
+            physical_size.height / hidpi_factor as f64,
+        );
+        Self { logical_size, physical_size, hidpi_factor }
+    }
+}
+
+/// Events the windowing backend (glutin, or the `wayland` listener) feeds
+/// into a `Window`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum WindowEvent {
+    Resized(HidpiAdjustedBounds),
+    CloseRequested,
+    /// The compositor reported a new preferred scale factor for the
+    /// surface - see `wayland::WaylandScaleFactorListener`, which is the
+    /// only producer of this event today.
+    #[cfg(feature = "wayland")]
+    ScaleFactorChanged(ScaleFactorChange),
+}
+
+/// Which [`Renderer`] backend a `Window` drives its GL context through.
+/// Defaults to the bundled WebRender backend; an embedder that already owns
+/// a GL context and compositor can supply its own via `Custom` instead.
+pub enum RendererType {
+    #[cfg(feature = "webrender")]
+    WebRender,
+    Custom(Box<dyn Renderer>),
+}
+
+#[cfg(feature = "webrender")]
+impl Default for RendererType {
+    fn default() -> Self {
+        RendererType::WebRender
+    }
+}
+
+/// Options used to create a [`Window`].
+pub struct WindowCreateOptions {
+    pub title: String,
+    pub size: LogicalSize,
+    /// Transparency / decoration settings this window's GL context and
+    /// compositor clear path are built around - see `window_transparency`.
+    pub transparent_options: WindowTransparencyOptions,
+    pub renderer_type: RendererType,
+}
+
+#[cfg(feature = "webrender")]
+impl Default for WindowCreateOptions {
+    fn default() -> Self {
+        Self {
+            title: "Azul App".into(),
+            size: LogicalSize::new(800.0, 600.0),
+            transparent_options: WindowTransparencyOptions::default(),
+            renderer_type: RendererType::default(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum WindowCreateError {
+    Renderer(String),
+}
+
+/// An open window: a GL context, the [`Renderer`] driving it, and the
+/// [`Compositor`] that wraps frame composition in profiler scopes and
+/// resolves the per-frame clear color.
+pub struct Window {
+    pub(crate) compositor: Compositor,
+    /// Shaped/laid-out text cached for this window's nodes. Dropped whole
+    /// on a HiDPI scale-factor change (`handle_event`) - a cached layout's
+    /// glyph positions are device pixels at the old scale factor, so none
+    /// of them are still correct at the new one.
+    pub(crate) text_cache: TextCache,
+    hidpi_factor: f32,
+    /// Set by a resize / scale-factor-changed event and cleared once the
+    /// next frame has re-run layout at the new bounds.
+    needs_reflow: bool,
+}
+
+/// Builds the `glutin` windowed GL context a [`Window`] is constructed
+/// from. This is the half of transparency support `window_transparency`'s
+/// module doc promises but that living purely in `compositor`'s clear color
+/// can never deliver: requesting an alpha-capable pixel format has to
+/// happen here, at context creation, or the platform compositor has no
+/// alpha channel to blend the window against no matter what `compositor`
+/// later clears to.
+#[cfg(feature = "webrender")]
+pub fn build_windowed_context(
+    options: &WindowCreateOptions,
+    events_loop: &::glium::glutin::EventsLoop,
+) -> Result<::glium::glutin::WindowedContext<::glium::glutin::PossiblyCurrent>, WindowCreateError> {
+    let window_builder = ::glium::glutin::WindowBuilder::new()
+        .with_title(options.title.clone())
+        .with_dimensions(options.size)
+        .with_decorations(options.transparent_options.decorations == Decorations::Full)
+        .with_transparency(options.transparent_options.transparent);
+
+    let alpha_bits = match options.transparent_options.framebuffer_alpha_mode() {
+        FramebufferAlphaMode::Alpha => 8,
+        FramebufferAlphaMode::Opaque => 0,
+    };
+
+    let windowed_context = ::glium::glutin::ContextBuilder::new()
+        .with_pixel_format(24, alpha_bits)
+        .build_windowed(window_builder, events_loop)
+        .map_err(|e| WindowCreateError::Renderer(format!("{:?}", e)))?;
+
+    unsafe {
+        windowed_context.make_current()
+            .map_err(|(_, e)| WindowCreateError::Renderer(format!("{:?}", e)))
+    }
+}
+
+/// Loads the `Gl` function pointer table from an already-current windowed
+/// context, for handing to [`Window::new`].
+#[cfg(feature = "webrender")]
+pub fn load_gl(windowed_context: &::glium::glutin::WindowedContext<::glium::glutin::PossiblyCurrent>) -> Rc<dyn Gl> {
+    unsafe {
+        ::gleam::gl::GlFns::load_with(|symbol| windowed_context.get_proc_address(symbol) as *const _)
+    }
+}
+
+impl Window {
+    /// Builds a window's GL-context-driving side: the requested
+    /// [`RendererType`] (WebRender by default) wrapped in a [`Compositor`].
+    /// `gl` must already have been created with the framebuffer alpha mode
+    /// `transparent_options` asks for (see [`build_windowed_context`]).
+    #[cfg(feature = "webrender")]
+    pub fn new(
+        options: WindowCreateOptions,
+        gl: Rc<dyn Gl>,
+        device_size: ::webrender::api::units::DeviceIntSize,
+    ) -> Result<Self, WindowCreateError> {
+        let profiler = Profiler::new(Rc::clone(&gl));
+
+        let renderer: Box<dyn Renderer> = match options.renderer_type {
+            RendererType::WebRender => {
+                // `transparent_options.framebuffer_alpha_mode()` is what the
+                // caller needs to have already requested from glutin's
+                // `ContextBuilder::with_pixel_format` before handing us
+                // `gl` - an alpha-capable context has to exist before
+                // WebRender's `Renderer` is created on top of it.
+                let webrender_options = ::webrender::RendererOptions {
+                    clear_color: None,
+                    ..::webrender::RendererOptions::default()
+                };
+                let (internal, sender) = ::webrender::Renderer::new(
+                    gl,
+                    Box::new(NoopNotifier),
+                    webrender_options,
+                    None,
+                    device_size,
+                ).map_err(|e| WindowCreateError::Renderer(format!("{:?}", e)))?;
+
+                Box::new(WebRenderRenderer::new(internal, sender, device_size))
+            }
+            RendererType::Custom(renderer) => renderer,
+        };
+
+        Ok(Self {
+            compositor: Compositor::new(renderer, profiler, options.transparent_options),
+            text_cache: TextCache::new(),
+            hidpi_factor: 1.0,
+            needs_reflow: false,
+        })
+    }
+
+    /// Applies a `WindowEvent` the platform backend produced. A resize
+    /// re-sizes the backend framebuffer (the backend rescales its glyph
+    /// atlas to match); a scale-factor change additionally drops the cached
+    /// text layouts and marks the window as needing a reflow on the next frame,
+    /// same as `wayland`'s module doc has always promised.
+    pub fn handle_event(&mut self, event: WindowEvent) {
+        match event {
+            WindowEvent::Resized(bounds) => {
+                self.compositor.resize(bounds);
+                self.needs_reflow = true;
+            }
+            WindowEvent::CloseRequested => {}
+            #[cfg(feature = "wayland")]
+            WindowEvent::ScaleFactorChanged(change) => {
+                self.compositor.resize(change.new_bounds);
+                self.hidpi_factor = change.new_hidpi_factor;
+                if change.needs_cache_invalidation() {
+                    self.compositor.invalidate_dpi_caches();
+                    self.text_cache.invalidate_all();
+                }
+                self.needs_reflow = true;
+            }
+        }
+    }
+
+    /// Draws one frame: makes sure every image the frame references has a
+    /// backend texture, submits the display list with `root_background`'s
+    /// clear color, composites `user_gl_textures` on top, then presents.
+    /// Clears `needs_reflow` - a caller is expected to have already re-run
+    /// layout against the latest `resources` before calling this.
+    pub fn render(
+        &mut self,
+        resources: &AppResources,
+        referenced_images: &[ImageId],
+        root_background: Option<ColorU>,
+        user_gl_textures: &[UserGlTexture],
+    ) -> Vec<ScopeTiming> {
+        let timings = self.compositor.composite(resources, referenced_images, root_background, user_gl_textures);
+        self.needs_reflow = false;
+        timings
+    }
+
+    pub fn hidpi_factor(&self) -> f32 {
+        self.hidpi_factor
+    }
+
+    pub fn needs_reflow(&self) -> bool {
+        self.needs_reflow
+    }
+}
+
+/// Iterator over the monitors attached to the system, as reported by glutin.
+pub struct MonitorIter(pub(crate) Vec<::glium::glutin::MonitorId>);
+
+impl Iterator for MonitorIter {
+    type Item = ::glium::glutin::MonitorId;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.pop()
+    }
+}
+
+/// Which monitor a window should open on.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum WindowMonitorTarget {
+    Primary,
+    Index(usize),
+}
+
+impl Default for WindowMonitorTarget {
+    fn default() -> Self {
+        WindowMonitorTarget::Primary
+    }
+}
+
+/// Whether the cursor is captured (for drag-to-look / drawing apps) or
+/// behaves like a normal desktop cursor.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MouseMode {
+    Normal,
+    Captured,
+}
+
+impl Default for MouseMode {
+    fn default() -> Self {
+        MouseMode::Normal
+    }
+}
+
+/// Whether a `Layout::layout` call this frame should trigger a full relayout
+/// (`ReRenderDom`, the default) or assumes the `Dom` is unchanged and only
+/// the backing data changed (`DoNothing`).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum UpdateBehaviour {
+    ReRenderDom,
+    DoNothing,
+}
+
+impl Default for UpdateBehaviour {
+    fn default() -> Self {
+        UpdateBehaviour::ReRenderDom
+    }
+}
+
+/// How often a window should redraw in the absence of any other event
+/// (animations / timers vs. a purely event-driven, idle-until-input window).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum UpdateMode {
+    AsFastAsPossible,
+    WaitForEvents,
+}
+
+impl Default for UpdateMode {
+    fn default() -> Self {
+        UpdateMode::WaitForEvents
+    }
+}
+
+/// Read-only, cross-thread-safe handle to a window, handed to background
+/// `task`/`daemon` callbacks that need to query (but not mutate) window state.
+pub struct ReadOnlyWindow {
+    pub(crate) hidpi_factor: f32,
+}
+
+/// Per-frame context `Layout::layout` receives: the window's current state,
+/// sized for the data model `T` so callbacks can be looked up without a cast.
+pub struct WindowInfo<T> {
+    pub window: ReadOnlyWindow,
+    _marker: ::std::marker::PhantomData<T>,
+}
+
+impl<T> WindowInfo<T> {
+    pub(crate) fn new(window: ReadOnlyWindow) -> Self {
+        Self { window, _marker: ::std::marker::PhantomData }
+    }
+}
+
+/// WebRender needs a `RenderNotifier` to wake the event loop up when a
+/// frame finishes rendering asynchronously; `compositor` drives rendering
+/// synchronously from `Window::render` instead, so
+/// there's nothing to wake.
+#[cfg(feature = "webrender")]
+#[derive(Clone)]
+struct NoopNotifier;
+
+#[cfg(feature = "webrender")]
+impl ::webrender::api::RenderNotifier for NoopNotifier {
+    fn clone(&self) -> Box<dyn ::webrender::api::RenderNotifier> {
+        Box::new(NoopNotifier)
+    }
+
+    fn wake_up(&self) {}
+
+    fn new_frame_ready(
+        &self,
+        _: ::webrender::api::DocumentId,
+        _scrolled: bool,
+        _composite_needed: bool,
+        _render_time: Option<u64>,
+    ) {
+    }
+}
@@ -0,0 +1,118 @@
+//! Transparent and decorationless window support.
+//!
+//! `WindowCreateOptions` embeds a [`WindowTransparencyOptions`] (as
+//! `transparent_options`), which carries the two new settings:
+//!
+//! - `transparent: bool` - requests an alpha-capable GL framebuffer and
+//!   makes the compositor's clear color respect a transparent root
+//!   background instead of always clearing to opaque.
+//! - `decorations: Decorations` - requests the normal OS-drawn decorations
+//!   or none at all, for overlays, HUDs and custom-chrome apps.
+//!
+//! Both options have a well-known pitfall if only half-wired: a window
+//! created without decorations but with an opaque framebuffer just shows a
+//! solid rectangle, and a window with an alpha framebuffer but no
+//! corresponding change to the compositor's clear color reverts to opaque
+//! the moment anything redraws. [`FramebufferAlphaMode`] and
+//! [`ClearColorMode`] exist specifically so `window`'s GL context creation
+//! (via [`WindowTransparencyOptions::framebuffer_alpha_mode`]) and
+//! `compositor`'s per-frame clear (via [`ClearColorMode::resolve`]) stay in
+//! sync on this.
+
+use azul_css::ColorU;
+
+/// Whether the window manager draws the title bar / borders, or the app
+/// draws its own (or none at all).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Decorations {
+    /// Normal OS-drawn title bar and borders (default)
+    Full,
+    /// No decorations at all - the app is responsible for its own chrome
+    /// (dragging, resize handles, close button, ...) if it wants any
+    None,
+}
+
+impl Default for Decorations {
+    fn default() -> Self {
+        Decorations::Full
+    }
+}
+
+/// Whether the GL context backing a window should request an alpha channel.
+/// Requesting one is required for `transparent: true` to have any effect -
+/// without it the platform compositor has nothing to blend the window
+/// against and it renders as opaque regardless of clear color.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FramebufferAlphaMode {
+    /// RGB only; the window is always opaque (default)
+    Opaque,
+    /// RGBA; required for a translucent or fully transparent window
+    Alpha,
+}
+
+impl FramebufferAlphaMode {
+    /// The mode a `WindowCreateOptions` with the given `transparent` flag
+    /// needs its GL context created with.
+    pub fn for_transparent(transparent: bool) -> Self {
+        if transparent { FramebufferAlphaMode::Alpha } else { FramebufferAlphaMode::Opaque }
+    }
+}
+
+/// What the compositor should clear the frame to before drawing the root
+/// node. Computed from the window's transparency setting and the root
+/// node's CSS `background`, so a `background: transparent` root on a
+/// `transparent: true` window actually shows the desktop through instead of
+/// silently falling back to an opaque clear.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ClearColorMode {
+    /// Clear to this (necessarily opaque, alpha = 255) color
+    Opaque(ColorU),
+    /// Clear to fully transparent - only valid on a window created with
+    /// `FramebufferAlphaMode::Alpha`
+    Transparent,
+}
+
+impl ClearColorMode {
+    /// Resolves the clear color the compositor should use for a frame,
+    /// given the window's transparency setting and the root node's
+    /// resolved CSS background color (`None` if the root background is
+    /// itself `transparent`).
+    pub fn resolve(window_transparent: bool, root_background: Option<ColorU>) -> Self {
+        match (window_transparent, root_background) {
+            (true, None) => ClearColorMode::Transparent,
+            // A root background with its own alpha < 255 on a transparent
+            // window should still let the desktop show through underneath
+            // it; clearing to that (possibly translucent) color first would
+            // make `Opaque` lie about always being fully opaque, so clear to
+            // fully transparent instead and let the translucent root
+            // background be blended over the transparent clear.
+            (true, Some(color)) if color.a < 255 => ClearColorMode::Transparent,
+            (_, Some(color)) => ClearColorMode::Opaque(ColorU { a: 255, ..color }),
+            (false, None) => ClearColorMode::Opaque(ColorU { r: 255, g: 255, b: 255, a: 255 }),
+        }
+    }
+}
+
+/// The two [`WindowCreateOptions`] settings this module backs.
+///
+/// `WindowCreateOptions` embeds this as `transparent_options` (holding
+/// `transparent` / `decorations`) rather than exposing [`FramebufferAlphaMode`] /
+/// [`ClearColorMode`] at construction time, since those are *derived*
+/// values - [`FramebufferAlphaMode::for_transparent`] and
+/// [`ClearColorMode::resolve`] compute them from this plus the root node's
+/// resolved CSS background, which isn't known until the first layout.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct WindowTransparencyOptions {
+    /// Requests an alpha-capable GL framebuffer; see [`FramebufferAlphaMode`].
+    pub transparent: bool,
+    /// Requests the normal OS-drawn decorations or none at all.
+    pub decorations: Decorations,
+}
+
+impl WindowTransparencyOptions {
+    /// The [`FramebufferAlphaMode`] `window`'s GL context creation should
+    /// request for a window created with these options.
+    pub fn framebuffer_alpha_mode(&self) -> FramebufferAlphaMode {
+        FramebufferAlphaMode::for_transparent(self.transparent)
+    }
+}